@@ -0,0 +1,69 @@
+use std::io::{Read, Write};
+
+use crate::error::SwapVecError;
+use crate::swapvec::{Compression, CompressionLevel};
+
+/// Turn a batch's serialized bytes into what actually ends up on
+/// disk, and back. Implemented on `Option<Compression>` so "no
+/// compression configured" is just a pass-through.
+pub(crate) trait Compress {
+    /// Compress `data` into `out` instead of allocating a fresh
+    /// `Vec<u8>`. `out` is cleared first; its capacity is reused as
+    /// far as it goes.
+    fn compress_into(&self, data: &[u8], out: &mut Vec<u8>);
+    fn decompress(&self, data: Vec<u8>) -> Result<Vec<u8>, SwapVecError>;
+}
+
+impl Compress for Option<Compression> {
+    fn compress_into(&self, data: &[u8], out: &mut Vec<u8>) {
+        out.clear();
+        match self {
+            None => out.extend_from_slice(data),
+            Some(Compression::Lz4) => {
+                // Mirrors `lz4_flex::block::compress_prepend_size`,
+                // but into `out`'s retained capacity instead of a
+                // fresh allocation: a 4-byte little-endian
+                // uncompressed length, then the compressed block.
+                let bound = lz4_flex::block::get_maximum_output_size(data.len());
+                out.resize(4 + bound, 0);
+                let compressed_len = lz4_flex::block::compress_into(data, &mut out[4..])
+                    .expect("out was sized via get_maximum_output_size");
+                out[..4].copy_from_slice(&(data.len() as u32).to_le_bytes());
+                out.truncate(4 + compressed_len);
+            }
+            Some(Compression::Deflate(level)) => {
+                let mut encoder = flate2::write::DeflateEncoder::new(out, deflate_level(*level));
+                encoder
+                    .write_all(data)
+                    .expect("writing into a Vec<u8> cannot fail");
+                encoder
+                    .finish()
+                    .expect("writing into a Vec<u8> cannot fail");
+            }
+        }
+    }
+
+    fn decompress(&self, data: Vec<u8>) -> Result<Vec<u8>, SwapVecError> {
+        match self {
+            None => Ok(data),
+            Some(Compression::Lz4) => lz4_flex::block::decompress_size_prepended(&data)
+                .map_err(|_| SwapVecError::WrongChecksum),
+            Some(Compression::Deflate(_)) => {
+                let mut decoder = flate2::read::DeflateDecoder::new(data.as_slice());
+                let mut out = Vec::new();
+                decoder
+                    .read_to_end(&mut out)
+                    .map_err(|_| SwapVecError::WrongChecksum)?;
+                Ok(out)
+            }
+        }
+    }
+}
+
+fn deflate_level(level: CompressionLevel) -> flate2::Compression {
+    match level {
+        CompressionLevel::Slow => flate2::Compression::best(),
+        CompressionLevel::Default => flate2::Compression::default(),
+        CompressionLevel::Fast => flate2::Compression::fast(),
+    }
+}
@@ -0,0 +1,46 @@
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+use crate::{error::SwapVecError, swapvec::Encryption};
+
+pub(crate) const NONCE_LEN: usize = 12;
+
+/// Encrypt `plaintext` (already serialized and, if configured,
+/// compressed) for batch number `batch_index`, prefixing the
+/// output with the nonce the batch was sealed with.
+pub(crate) fn encrypt(encryption: &Encryption, batch_index: u64, plaintext: &[u8]) -> Vec<u8> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(encryption.key()));
+    let nonce_bytes = nonce_for(batch_index);
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .expect("encrypting an in-memory buffer with a fresh nonce cannot fail");
+
+    let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&ciphertext);
+    out
+}
+
+/// Split the nonce off `data`, then verify and decrypt the rest.
+pub(crate) fn decrypt(encryption: &Encryption, data: Vec<u8>) -> Result<Vec<u8>, SwapVecError> {
+    if data.len() < NONCE_LEN {
+        return Err(SwapVecError::DecryptionFailed);
+    }
+    let (nonce_bytes, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(encryption.key()));
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| SwapVecError::DecryptionFailed)
+}
+
+/// Batch index, counting up from zero, turned into a nonce. Nonces
+/// are unique across the batches of one `SwapVec`; a second `SwapVec`
+/// using the same key starts again at batch 0 and reuses them.
+fn nonce_for(batch_index: u64) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[NONCE_LEN - 8..].copy_from_slice(&batch_index.to_be_bytes());
+    nonce
+}
@@ -11,6 +11,15 @@ pub enum SwapVecError {
     OutOfDisk,
     /// A read back batch had a wrong checksum
     WrongChecksum,
+    /// [`crate::SwapVec::verify`] recomputed the whole-file Merkle
+    /// root and it didn't match the one built when the batches were
+    /// written, meaning the spill file was truncated, reordered, or
+    /// otherwise corrupted.
+    IntegrityFailed,
+    /// A read back batch could not be authenticated/decrypted with
+    /// the configured `Encryption` key. Either the wrong key was
+    /// used, or the batch was truncated or tampered with.
+    DecryptionFailed,
     /// The batch was read back successfully,
     /// but the serialization failed.
     ///
@@ -22,8 +31,12 @@ pub enum SwapVecError {
 }
 
 impl From<std::io::Error> for SwapVecError {
-    fn from(_value: std::io::Error) -> Self {
-        todo!()
+    fn from(value: std::io::Error) -> Self {
+        match value.kind() {
+            std::io::ErrorKind::PermissionDenied => SwapVecError::MissingPermissions,
+            std::io::ErrorKind::WriteZero => SwapVecError::OutOfDisk,
+            _ => SwapVecError::Other,
+        }
     }
 }
 
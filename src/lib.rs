@@ -0,0 +1,27 @@
+//! # swapvec
+//!
+//! `SwapVec` behaves like a growable, only-appending vector that
+//! transparently spills its elements to a temporary file once it
+//! grows past a configured threshold, so huge iterators can be
+//! consumed without holding everything in RAM at once.
+//!
+//! ```rust
+//! let mut bigvec = swapvec::SwapVec::default();
+//! let iterator = 0..9;
+//! bigvec.consume(iterator);
+//! bigvec.push(99);
+//! let new_iterator = bigvec.into_iter();
+//! ```
+
+mod compression;
+mod crypto;
+pub mod error;
+mod merkle;
+mod swapvec;
+mod swapveciter;
+mod writer;
+
+pub use crate::swapvec::{
+    BatchInfo, Compression, CompressionLevel, Encryption, Integrity, SwapVec, SwapVecConfig,
+};
+pub use error::SwapVecError;
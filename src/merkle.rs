@@ -0,0 +1,61 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::swapvec::Integrity;
+
+/// Hash a batch's on-disk bytes into its [`BatchInfo`](crate::BatchInfo)
+/// checksum, which doubles as this batch's leaf in the whole-file
+/// Merkle tree.
+pub(crate) fn leaf_hash(buffer: &[u8], integrity: Integrity) -> Vec<u8> {
+    match integrity {
+        Integrity::Fast => {
+            let mut hasher = DefaultHasher::new();
+            buffer.hash(&mut hasher);
+            hasher.finish().to_le_bytes().to_vec()
+        }
+        Integrity::Cryptographic => Sha256::digest(buffer).to_vec(),
+    }
+}
+
+/// Hash two child digests together into their parent.
+fn node_hash(left: &[u8], right: &[u8], integrity: Integrity) -> Vec<u8> {
+    match integrity {
+        Integrity::Fast => {
+            let mut hasher = DefaultHasher::new();
+            left.hash(&mut hasher);
+            right.hash(&mut hasher);
+            hasher.finish().to_le_bytes().to_vec()
+        }
+        Integrity::Cryptographic => {
+            let mut hasher = Sha256::new();
+            hasher.update(left);
+            hasher.update(right);
+            hasher.finalize().to_vec()
+        }
+    }
+}
+
+/// Fold a list of leaf digests, in batch order, up into a single
+/// root. A level with an odd node out pairs it with itself, so
+/// every level still folds down to one digest.
+pub(crate) fn root(leaves: &[Vec<u8>], integrity: Integrity) -> Option<Vec<u8>> {
+    if leaves.is_empty() {
+        return None;
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right, integrity),
+                [only] => node_hash(only, only, integrity),
+                _ => unreachable!("chunks(2) never yields more than 2 items"),
+            })
+            .collect();
+    }
+    level.into_iter().next()
+}
@@ -1,9 +1,8 @@
 use std::{
-    collections::{hash_map::DefaultHasher, VecDeque},
+    collections::VecDeque,
     fmt::Debug,
     fs::File,
-    hash::{Hash, Hasher},
-    io::Write,
+    io::{Read, Seek, SeekFrom, Write},
 };
 
 #[cfg(any(unix, target_os = "wasi"))]
@@ -11,7 +10,10 @@ use std::os::unix::io::AsRawFd;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{compression::Compress, error::SwapVecError, swapveciter::SwapVecIter};
+use crate::{
+    compression::Compress, crypto, error::SwapVecError, merkle, swapveciter::SwapVecIter,
+    writer::{WriterPipeline, WriterPipelineConfig},
+};
 
 /// Set compression level of the compression
 /// algorithm. This maps to different values
@@ -42,6 +44,24 @@ pub enum Compression {
     Deflate(CompressionLevel),
 }
 
+/// How strongly batches, and the file as a whole, are checksummed.
+///
+/// Used both for the per-batch hash stored in [`BatchInfo`] and as
+/// the leaf/node hash for the whole-file Merkle tree built by
+/// [`SwapVec::root_hash`] and [`SwapVec::verify`].
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Integrity {
+    /// `std::collections::hash_map::DefaultHasher`. Fast, and
+    /// enough to catch accidental bit-rot or truncation, but not a
+    /// deliberate tamperer.
+    #[default]
+    Fast,
+    /// SHA-256. Slower, but tamper-evident: forging a matching
+    /// digest for a modified batch, or for a modified Merkle tree
+    /// built over them, isn't practical.
+    Cryptographic,
+}
+
 /// Configure when and how the vector should swap.
 ///
 /// The file creation will happen after max(swap_after, batch_size)
@@ -77,6 +97,48 @@ pub struct SwapVecConfig {
     ///
     /// Default: No compression
     pub compression: Option<Compression>,
+    /// Spill batches to disk from a background pipeline instead of
+    /// blocking `push`/`consume` on serialization, compression and
+    /// IO.
+    ///
+    /// `0` keeps the default, synchronous behaviour. Any other
+    /// value starts that many worker threads (plus one writer
+    /// thread) the first time a batch is flushed: workers
+    /// serialize and compress batches in parallel, and the writer
+    /// thread reorders their output back into push order before
+    /// writing it out, so the on-disk layout is unaffected.
+    ///
+    /// Call [`SwapVec::flush`] or [`SwapVec::finish`] to wait for
+    /// every in-flight batch to reach disk; `into_iter` does this
+    /// for you.
+    ///
+    /// Default: 0 (synchronous)
+    pub writer_threads: usize,
+    /// Encrypt (compress-then-encrypt) the temporary file with
+    /// ChaCha20-Poly1305, authenticating each batch.
+    ///
+    /// Since the AEAD tag already guarantees per-batch integrity,
+    /// the stored hash is no longer compared against on read; it's
+    /// still recorded and still feeds the whole-file Merkle tree,
+    /// since AEAD alone can't catch batches being reordered.
+    ///
+    /// Default: No encryption
+    pub encryption: Option<Encryption>,
+    /// How many compressed bytes to accumulate in memory before
+    /// issuing a real write/flush to the spill file.
+    ///
+    /// Batches are still recorded in `BatchInfo` one by one, so the
+    /// logical layout doesn't change; this only controls how often
+    /// the underlying syscalls happen. Call [`SwapVec::flush`] to
+    /// force whatever's buffered onto disk early.
+    ///
+    /// Default: 128 * 1024 (128 KiB)
+    pub write_buffer_bytes: usize,
+    /// How batch hashes (and the whole-file Merkle root built over
+    /// them) are computed.
+    ///
+    /// Default: `Integrity::Fast`
+    pub integrity: Integrity,
 }
 
 impl Default for SwapVecConfig {
@@ -85,34 +147,142 @@ impl Default for SwapVecConfig {
             swap_after: 32 * 1024 * 1024,
             batch_size: 32 * 1024,
             compression: None,
+            writer_threads: 0,
+            encryption: None,
+            write_buffer_bytes: 128 * 1024,
+            integrity: Integrity::default(),
         }
     }
 }
 
+/// A 256-bit key used to encrypt the temporary spill file with
+/// ChaCha20-Poly1305. See [`SwapVecConfig::encryption`].
+#[derive(Clone, Copy)]
+pub struct Encryption {
+    key: [u8; 32],
+}
+
+impl Encryption {
+    /// Encrypt every batch with `key`.
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    pub(crate) fn key(&self) -> &[u8; 32] {
+        &self.key
+    }
+}
+
+impl Debug for Encryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Encryption")
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
 pub struct BatchInfo {
-    pub hash: u64,
+    /// The batch's on-disk bytes, hashed per [`SwapVecConfig::integrity`];
+    /// also used as this batch's leaf in the whole-file Merkle tree.
+    pub hash: Vec<u8>,
     pub bytes: usize,
 }
 
 pub(crate) struct CheckedFile {
     pub file: File,
     pub batch_info: Vec<BatchInfo>,
+    write_buf: Vec<u8>,
+    write_buffer_bytes: usize,
+    /// Batches whose bytes are sitting in `write_buf` but haven't
+    /// survived a real write/flush yet. Only merged into
+    /// `batch_info` once `flush_buffer` actually succeeds, so a
+    /// failed flush can't leave `batch_info` pointing at bytes that
+    /// never made it to disk.
+    pending: Vec<BatchInfo>,
 }
 
 impl CheckedFile {
-    fn write_all(&mut self, buffer: &Vec<u8>) -> Result<(), std::io::Error> {
-        let mut hasher = DefaultHasher::new();
-        buffer.hash(&mut hasher);
-        self.file.write_all(buffer)?;
-        self.batch_info.push(BatchInfo {
-            hash: hasher.finish(),
+    pub(crate) fn new(file: File, write_buffer_bytes: usize) -> Self {
+        Self {
+            file,
+            batch_info: Vec::new(),
+            write_buf: Vec::new(),
+            write_buffer_bytes,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Wrap an already-written file whose batches are fully on
+    /// disk, e.g. handed back by the threaded writer pipeline.
+    pub(crate) fn from_parts(
+        file: File,
+        batch_info: Vec<BatchInfo>,
+        write_buffer_bytes: usize,
+    ) -> Self {
+        Self {
+            file,
+            batch_info,
+            write_buf: Vec::new(),
+            write_buffer_bytes,
+            pending: Vec::new(),
+        }
+    }
+
+    /// The inverse of [`Self::from_parts`]: hand the file and its
+    /// recorded batches back, e.g. to resume writing through a new
+    /// writer pipeline. Flushes first so nothing buffered is lost.
+    pub(crate) fn into_parts(mut self) -> Result<(File, Vec<BatchInfo>), std::io::Error> {
+        self.flush_buffer()?;
+        Ok((self.file, self.batch_info))
+    }
+
+    /// Record a batch and accumulate its bytes in the write buffer,
+    /// only issuing a real write/flush once the buffer crosses
+    /// `write_buffer_bytes`. The batch only becomes visible to
+    /// readers (via `batch_info`) once its bytes have actually been
+    /// flushed; until then it sits in `pending`.
+    pub(crate) fn write_all(
+        &mut self,
+        buffer: &Vec<u8>,
+        hash: Vec<u8>,
+    ) -> Result<(), std::io::Error> {
+        self.write_buf.extend_from_slice(buffer);
+        self.pending.push(BatchInfo {
+            hash,
             bytes: buffer.len(),
         });
-        self.file.flush()
+        if self.write_buf.len() >= self.write_buffer_bytes {
+            self.flush_buffer()?;
+        }
+        Ok(())
     }
 
+    /// Force whatever's accumulated in the write buffer onto disk.
+    ///
+    /// Only on success do the buffered batches move from `pending`
+    /// into `batch_info`; if the write fails, `pending` (and the
+    /// bytes still in `write_buf`) are left untouched, so a caller
+    /// that keeps using this `CheckedFile` after the error sees
+    /// reads cleanly stop at the last successfully flushed batch
+    /// instead of seeking into bytes that were never written.
+    pub(crate) fn flush_buffer(&mut self) -> Result<(), std::io::Error> {
+        if self.write_buf.is_empty() {
+            return Ok(());
+        }
+        self.file.write_all(&self.write_buf)?;
+        self.file.flush()?;
+        self.write_buf.clear();
+        self.batch_info.append(&mut self.pending);
+        Ok(())
+    }
+
+    /// Bytes durably on disk plus bytes still sitting in the write
+    /// buffer; the latter aren't readable yet, but they're real
+    /// file size once `flush_buffer` runs.
     fn file_size(&self) -> u64 {
-        self.batch_info.iter().map(|x| x.bytes as u64).sum()
+        let flushed: u64 = self.batch_info.iter().map(|x| x.bytes as u64).sum();
+        let buffered: u64 = self.pending.iter().map(|x| x.bytes as u64).sum();
+        flushed + buffered
     }
 }
 
@@ -135,6 +305,10 @@ where
     tempfile: Option<CheckedFile>,
     vector: VecDeque<T>,
     config: SwapVecConfig,
+    pipeline: Option<WriterPipeline<T>>,
+    flushed_batches: usize,
+    serialize_buf: Vec<u8>,
+    compress_buf: Vec<u8>,
 }
 
 impl<T: Serialize + for<'a> Deserialize<'a>> Default for SwapVec<T> {
@@ -143,6 +317,10 @@ impl<T: Serialize + for<'a> Deserialize<'a>> Default for SwapVec<T> {
             tempfile: None,
             vector: VecDeque::new(),
             config: SwapVecConfig::default(),
+            pipeline: None,
+            flushed_batches: 0,
+            serialize_buf: Vec::new(),
+            compress_buf: Vec::new(),
         }
     }
 }
@@ -158,11 +336,7 @@ impl<T: Serialize + for<'a> Deserialize<'a>> Debug for SwapVec<T> {
             f,
             "SwapVec {{elements_in_ram: {}, elements_in_file: {}, filedescriptor: {:#?}}}",
             self.vector.len(),
-            self.tempfile
-                .as_ref()
-                .map(|x| x.batch_info.len())
-                .unwrap_or(0)
-                * self.config.batch_size,
+            self.flushed_batches * self.config.batch_size,
             file_descriptor
         )
     }
@@ -178,12 +352,22 @@ where
             tempfile: None,
             vector: VecDeque::new(),
             config,
+            pipeline: None,
+            flushed_batches: 0,
+            serialize_buf: Vec::new(),
+            compress_buf: Vec::new(),
         }
     }
 
-    /// Give away an entire iterator for consumption.  
+    /// Give away an entire iterator for consumption.
     /// Might return an error, due to possibly triggered batch flush (IO).
-    pub fn consume(&mut self, it: impl Iterator<Item = T>) -> Result<(), SwapVecError> {
+    ///
+    /// Requires `T: Send + 'static` since a full batch may be handed
+    /// off to the threaded writer pipeline.
+    pub fn consume(&mut self, it: impl Iterator<Item = T>) -> Result<(), SwapVecError>
+    where
+        T: Send + 'static,
+    {
         for element in it {
             self.push(element)?;
             self.after_push_work()?;
@@ -193,7 +377,13 @@ where
 
     /// Push a single element.
     /// Might return an error, due to possibly triggered batch flush (IO).
-    pub fn push(&mut self, element: T) -> Result<(), SwapVecError> {
+    ///
+    /// Requires `T: Send + 'static` since a full batch may be handed
+    /// off to the threaded writer pipeline.
+    pub fn push(&mut self, element: T) -> Result<(), SwapVecError>
+    where
+        T: Send + 'static,
+    {
         self.vector.push_back(element);
         self.after_push_work()
     }
@@ -202,11 +392,16 @@ where
     /// the temporary file has been created.  
     /// Will be false if element count is below swap_after and below batch_size
     pub fn written_to_file(&self) -> bool {
-        self.tempfile.is_some()
+        self.is_spilling()
     }
 
     /// Get the file size in bytes of the temporary file.
     /// Might do IO and therefore could return some Result.
+    ///
+    /// While `writer_threads` is set and the writer pipeline is
+    /// running, the spill file is owned by the pipeline and this
+    /// returns `None`; call [`Self::flush`] first for an up-to-date
+    /// number.
     pub fn file_size(&self) -> Option<u64> {
         match self.tempfile.as_ref() {
             None => None,
@@ -214,48 +409,446 @@ where
         }
     }
 
-    /// Basically int(elements pushed / batch size)
+    /// Basically int(elements pushed / batch size).
     pub fn batches_written(&self) -> usize {
-        match self.tempfile.as_ref() {
-            None => 0,
-            Some(f) => f.batch_info.len(),
+        self.flushed_batches
+    }
+
+    /// Total number of elements pushed so far, whether still held
+    /// in RAM or already handed off to the spill file/pipeline.
+    pub fn len(&self) -> usize {
+        self.flushed_batches * self.config.batch_size + self.vector.len()
+    }
+
+    /// `true` if nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Wait for every batch still in flight in the threaded writer
+    /// pipeline to reach disk, surfacing any error a worker or the
+    /// writer thread ran into. A no-op when `writer_threads` is `0`.
+    ///
+    /// Requires `T: Send + 'static`, same as [`Self::push`].
+    pub fn flush(&mut self) -> Result<(), SwapVecError>
+    where
+        T: Send + 'static,
+    {
+        self.finish_pipeline()
+    }
+
+    /// Finalize the threaded writer pipeline (if any) and return
+    /// self, ready to be turned into an iterator. Equivalent to
+    /// [`Self::flush`] followed by using `self`; `into_iter` also
+    /// calls this for you.
+    ///
+    /// Requires `T: Send + 'static`, same as [`Self::push`].
+    pub fn finish(mut self) -> Result<Self, SwapVecError>
+    where
+        T: Send + 'static,
+    {
+        self.finish_pipeline()?;
+        Ok(self)
+    }
+
+    fn is_spilling(&self) -> bool {
+        self.tempfile.is_some() || self.pipeline.is_some()
+    }
+
+    fn finish_pipeline(&mut self) -> Result<(), SwapVecError>
+    where
+        T: Send + 'static,
+    {
+        if let Some(pipeline) = self.pipeline.take() {
+            let (file, batch_info) = pipeline.finish()?;
+            self.tempfile = Some(CheckedFile::from_parts(
+                file,
+                batch_info,
+                self.config.write_buffer_bytes,
+            ));
         }
+        Ok(())
     }
 
-    fn after_push_work(&mut self) -> Result<(), SwapVecError> {
+    fn after_push_work(&mut self) -> Result<(), SwapVecError>
+    where
+        T: Send + 'static,
+    {
+        if let Some(pipeline) = self.pipeline.as_ref() {
+            pipeline.check_error()?;
+        }
+
         if self.vector.len() <= self.config.batch_size {
             return Ok(());
         }
-        if self.tempfile.is_none() && self.vector.len() <= self.config.swap_after {
+        if !self.is_spilling() && self.vector.len() <= self.config.swap_after {
             return Ok(());
         }
 
-        // Flush batch
-        if self.tempfile.is_none() {
-            let tf = tempfile::tempfile()?;
-            self.tempfile = Some(CheckedFile {
-                file: tf,
-                batch_info: Vec::new(),
-            })
-        }
         let batch: Vec<T> = (0..self.config.batch_size)
             .map(|_| self.vector.pop_front().unwrap())
             .collect::<Vec<_>>();
         // TODO: shrink self.vector by writing double
         // sized batches and calling self.vector.shrink_to()
+        let batch_index = self.flushed_batches as u64;
+        self.flushed_batches += 1;
+
+        if self.config.writer_threads > 0 {
+            if self.pipeline.is_none() {
+                // Resume from whatever a previously-finished pipeline
+                // generation left behind, instead of opening a brand
+                // new (and so far empty) spill file: otherwise every
+                // `flush` followed by more pushes would silently
+                // orphan everything written so far.
+                let (file, batch_info) = match self.tempfile.take() {
+                    Some(checked_file) => checked_file.into_parts()?,
+                    None => (tempfile::tempfile()?, Vec::new()),
+                };
+                let start_seq = batch_info.len() as u64;
+                self.pipeline = Some(WriterPipeline::start(WriterPipelineConfig {
+                    file,
+                    worker_threads: self.config.writer_threads,
+                    compression: self.config.compression,
+                    encryption: self.config.encryption,
+                    write_buffer_bytes: self.config.write_buffer_bytes,
+                    integrity: self.config.integrity,
+                    existing_batch_info: batch_info,
+                    start_seq,
+                }));
+            }
+            self.pipeline.as_mut().unwrap().submit(batch_index, batch)?;
+        } else {
+            if self.tempfile.is_none() {
+                let tf = tempfile::tempfile()?;
+                self.tempfile = Some(CheckedFile::new(tf, self.config.write_buffer_bytes));
+            }
+            self.serialize_buf.clear();
+            bincode::serialize_into(&mut self.serialize_buf, &batch)?;
+            self.config
+                .compression
+                .compress_into(&self.serialize_buf, &mut self.compress_buf);
+
+            match self.config.encryption.as_ref() {
+                Some(encryption) => {
+                    let sealed = crypto::encrypt(encryption, batch_index, &self.compress_buf);
+                    let hash = merkle::leaf_hash(&sealed, self.config.integrity);
+                    self.tempfile.as_mut().unwrap().write_all(&sealed, hash)?;
+                }
+                None => {
+                    let hash = merkle::leaf_hash(&self.compress_buf, self.config.integrity);
+                    self.tempfile
+                        .as_mut()
+                        .unwrap()
+                        .write_all(&self.compress_buf, hash)?;
+                }
+            }
+        }
+        Ok(())
+    }
 
-        let buffer = bincode::serialize(&batch)?;
-        let compressed = self.config.compression.compress(buffer);
-        self.tempfile.as_mut().unwrap().write_all(&compressed)?;
+    /// The root of the Merkle tree built over every flushed batch's
+    /// hash, in write order. `None` until at least one batch has
+    /// reached the spill file.
+    ///
+    /// Waits for the threaded writer pipeline (if any) to finish
+    /// first, since the tree isn't final until every batch is on
+    /// disk.
+    ///
+    /// Requires `T: Send + 'static`, same as [`Self::push`].
+    pub fn root_hash(&mut self) -> Result<Option<[u8; 32]>, SwapVecError>
+    where
+        T: Send + 'static,
+    {
+        self.finish_pipeline()?;
+        let leaves: Vec<Vec<u8>> = match self.tempfile.as_mut() {
+            Some(tempfile) => {
+                tempfile.flush_buffer()?;
+                tempfile.batch_info.iter().map(|i| i.hash.clone()).collect()
+            }
+            None => return Ok(None),
+        };
+        Ok(merkle::root(&leaves, self.config.integrity).map(|root| pad_to_32(&root)))
+    }
+
+    /// Re-read every flushed batch from the spill file, recompute
+    /// its leaf hash from the actual on-disk bytes, rebuild the
+    /// Merkle tree, and compare it against [`Self::root_hash`].
+    ///
+    /// Unlike [`Self::get`] or iterating, this never decompresses,
+    /// decrypts or deserializes a batch — it only hashes bytes — so
+    /// it catches truncation, reordering and bit-rot cheaply,
+    /// without needing a valid `T`.
+    ///
+    /// `Ok(())` if nothing was ever spilled to disk.
+    ///
+    /// Requires `T: Send + 'static`, same as [`Self::push`].
+    pub fn verify(&mut self) -> Result<(), SwapVecError>
+    where
+        T: Send + 'static,
+    {
+        let expected_root = match self.root_hash()? {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+
+        let tempfile = self
+            .tempfile
+            .as_mut()
+            .expect("root_hash returned Some, so a spill file exists");
+        tempfile.file.seek(SeekFrom::Start(0))?;
+
+        let mut leaves = Vec::with_capacity(tempfile.batch_info.len());
+        for info in &tempfile.batch_info {
+            let mut buffer = vec![0u8; info.bytes];
+            tempfile.file.read_exact(&mut buffer)?;
+            leaves.push(merkle::leaf_hash(&buffer, self.config.integrity));
+        }
+        tempfile.file.seek(SeekFrom::End(0))?;
+
+        let recomputed = merkle::root(&leaves, self.config.integrity)
+            .expect("root_hash returned Some, so at least one batch exists");
+
+        if pad_to_32(&recomputed) != expected_root {
+            return Err(SwapVecError::IntegrityFailed);
+        }
         Ok(())
     }
 }
 
-impl<T: Serialize + for<'a> Deserialize<'a>> IntoIterator for SwapVec<T> {
+/// Fit a digest (8 bytes for `Integrity::Fast`, 32 for
+/// `Integrity::Cryptographic`) into a fixed-size array, zero-padding
+/// on the right if it's shorter.
+fn pad_to_32(digest: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[..digest.len()].copy_from_slice(digest);
+    out
+}
+
+impl<T> SwapVec<T>
+where
+    for<'a> T: Serialize + Deserialize<'a> + Send + 'static + Clone,
+{
+    /// Read back a single element by index, without consuming the
+    /// vector or replaying everything before it.
+    ///
+    /// Maps `index` onto its flushed batch from the recorded batch
+    /// byte lengths, seeks straight to that batch's offset in the
+    /// spill file, and decodes only it; indices past the flushed
+    /// batches are served from the in-RAM tail. Waits for the
+    /// threaded writer pipeline (if any) to finish first, since
+    /// batch offsets aren't final until every batch is on disk.
+    ///
+    /// Takes `&mut self` rather than `&self`: reading a flushed batch
+    /// seeks the shared spill file handle and may first have to wait
+    /// on the writer pipeline and flush pending writes, none of
+    /// which is possible through a shared reference without adding
+    /// interior mutability. `T: Clone` is needed for the same reason
+    /// as the in-RAM fallback: unlike the spilled-batch path (which
+    /// always produces a fresh, owned `T` via `bincode::deserialize`
+    /// anyway), returning an *in-RAM* element by value without
+    /// removing it from `self.vector` has no way to hand over
+    /// ownership except by cloning it.
+    pub fn get(&mut self, index: usize) -> Result<Option<T>, SwapVecError> {
+        if index >= self.len() {
+            return Ok(None);
+        }
+
+        let flushed = self.flushed_batches * self.config.batch_size;
+        if index >= flushed {
+            return Ok(self.vector.get(index - flushed).cloned());
+        }
+
+        self.finish_pipeline()?;
+        let tempfile = self
+            .tempfile
+            .as_mut()
+            .expect("flushed_batches > 0 implies a spill file was created");
+        tempfile.flush_buffer()?;
+
+        let batch_index = index / self.config.batch_size;
+        let offset_in_batch = index % self.config.batch_size;
+        let offset: u64 = tempfile.batch_info[..batch_index]
+            .iter()
+            .map(|info| info.bytes as u64)
+            .sum();
+        let info = &tempfile.batch_info[batch_index];
+        let (info_bytes, info_hash) = (info.bytes, info.hash.clone());
+
+        tempfile.file.seek(SeekFrom::Start(offset))?;
+        let mut buffer = vec![0u8; info_bytes];
+        tempfile.file.read_exact(&mut buffer)?;
+
+        if self.config.encryption.is_none()
+            && merkle::leaf_hash(&buffer, self.config.integrity) != info_hash
+        {
+            return Err(SwapVecError::WrongChecksum);
+        }
+
+        let unsealed = match self.config.encryption.as_ref() {
+            Some(encryption) => crypto::decrypt(encryption, buffer)?,
+            None => buffer,
+        };
+        let decompressed = self.config.compression.decompress(unsealed)?;
+        let batch: Vec<T> = bincode::deserialize(&decompressed)?;
+
+        // Put the cursor back at the end so the next flushed batch
+        // keeps appending instead of overwriting what we just read.
+        tempfile.file.seek(SeekFrom::End(0))?;
+
+        Ok(batch.into_iter().nth(offset_in_batch))
+    }
+}
+
+impl<T: Serialize + for<'a> Deserialize<'a> + Send + 'static> IntoIterator for SwapVec<T> {
     type Item = Result<T, SwapVecError>;
     type IntoIter = SwapVecIter<T>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        SwapVecIter::new(self.tempfile, self.vector, self.config)
+    fn into_iter(mut self) -> Self::IntoIter {
+        let pending_error = self
+            .finish_pipeline()
+            .and_then(|()| match self.tempfile.as_mut() {
+                Some(tempfile) => tempfile.flush_buffer().map_err(SwapVecError::from),
+                None => Ok(()),
+            })
+            .err();
+        SwapVecIter::new(self.tempfile, self.vector, self.config, pending_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `/dev/full` always fails a write with `ENOSPC`, which is the
+    /// simplest way to force `flush_buffer` to fail without mocking
+    /// the filesystem.
+    #[cfg(unix)]
+    #[test]
+    fn failed_flush_does_not_record_unwritten_batches() {
+        let file = File::options()
+            .write(true)
+            .open("/dev/full")
+            .expect("/dev/full should be available on this platform");
+        let mut checked_file = CheckedFile::new(file, 1);
+
+        let result = checked_file.write_all(&vec![1, 2, 3], vec![0]);
+
+        assert!(result.is_err());
+        assert!(checked_file.batch_info.is_empty());
+    }
+
+    /// Regression test for a pipeline-restart bug: `flush`ing a
+    /// pipelined `SwapVec` used to overwrite `self.tempfile` with a
+    /// brand new, empty spill file the next time a batch flushed,
+    /// silently dropping every batch written by the previous
+    /// pipeline generation.
+    #[test]
+    fn flushing_a_pipeline_twice_preserves_earlier_batches() {
+        let config = SwapVecConfig {
+            batch_size: 10,
+            swap_after: 10,
+            writer_threads: 1,
+            ..Default::default()
+        };
+        let mut bigvec: SwapVec<i32> = SwapVec::with_config(config);
+
+        for i in 0..25 {
+            bigvec.push(i).unwrap();
+        }
+        bigvec.flush().unwrap();
+
+        for i in 25..45 {
+            bigvec.push(i).unwrap();
+        }
+        bigvec.flush().unwrap();
+
+        let result: Vec<i32> = bigvec.into_iter().map(|x| x.unwrap()).collect();
+        assert_eq!(result, (0..45).collect::<Vec<i32>>());
+    }
+
+    /// Regression test for nonce reuse: before each pipeline
+    /// generation derived its nonces from a counter that restarted
+    /// at 0, a `flush` followed by more pushes would re-encrypt
+    /// batches under nonces already used by the first generation,
+    /// under the same key.
+    #[test]
+    fn encrypted_pipeline_restart_never_reuses_a_nonce() {
+        let config = SwapVecConfig {
+            batch_size: 10,
+            swap_after: 10,
+            writer_threads: 1,
+            encryption: Some(Encryption::new([7u8; 32])),
+            ..Default::default()
+        };
+        let mut bigvec: SwapVec<i32> = SwapVec::with_config(config);
+
+        for i in 0..25 {
+            bigvec.push(i).unwrap();
+        }
+        bigvec.flush().unwrap();
+        for i in 25..45 {
+            bigvec.push(i).unwrap();
+        }
+        bigvec.flush().unwrap();
+
+        let tempfile = bigvec.tempfile.as_mut().expect("batches were spilled");
+        tempfile.file.seek(SeekFrom::Start(0)).unwrap();
+        let mut seen_nonces = std::collections::HashSet::new();
+        for info in &tempfile.batch_info {
+            let mut buffer = vec![0u8; info.bytes];
+            tempfile.file.read_exact(&mut buffer).unwrap();
+            let nonce = buffer[..crypto::NONCE_LEN].to_vec();
+            assert!(
+                seen_nonces.insert(nonce),
+                "nonce reused across pipeline generations"
+            );
+        }
+        tempfile.file.seek(SeekFrom::End(0)).unwrap();
+
+        let result: Vec<i32> = bigvec.into_iter().map(|x| x.unwrap()).collect();
+        assert_eq!(result, (0..45).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn get_reads_both_spilled_and_in_ram_elements() {
+        let config = SwapVecConfig {
+            batch_size: 10,
+            swap_after: 10,
+            ..Default::default()
+        };
+        let mut bigvec: SwapVec<i32> = SwapVec::with_config(config);
+        for i in 0..25 {
+            bigvec.push(i).unwrap();
+        }
+        assert!(bigvec.written_to_file());
+
+        for i in 0..25 {
+            assert_eq!(bigvec.get(i as usize).unwrap(), Some(i));
+        }
+        assert_eq!(bigvec.get(25).unwrap(), None);
+    }
+
+    #[test]
+    fn verify_detects_spill_file_corruption() {
+        let config = SwapVecConfig {
+            batch_size: 10,
+            swap_after: 10,
+            ..Default::default()
+        };
+        let mut bigvec: SwapVec<i32> = SwapVec::with_config(config);
+        for i in 0..25 {
+            bigvec.push(i).unwrap();
+        }
+        bigvec.verify().unwrap();
+
+        let tempfile = bigvec.tempfile.as_mut().expect("batches were spilled");
+        tempfile.file.seek(SeekFrom::Start(0)).unwrap();
+        let mut first_byte = [0u8; 1];
+        tempfile.file.read_exact(&mut first_byte).unwrap();
+        tempfile.file.seek(SeekFrom::Start(0)).unwrap();
+        tempfile.file.write_all(&[!first_byte[0]]).unwrap();
+        tempfile.file.seek(SeekFrom::End(0)).unwrap();
+
+        assert!(matches!(bigvec.verify(), Err(SwapVecError::IntegrityFailed)));
     }
 }
@@ -0,0 +1,108 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    compression::Compress,
+    crypto,
+    error::SwapVecError,
+    merkle,
+    swapvec::{BatchInfo, CheckedFile, SwapVecConfig},
+};
+
+/// Iterator returned by [`SwapVec::into_iter`](crate::SwapVec).
+///
+/// First replays whatever was spilled to the temporary file, batch
+/// by batch, then drains whatever was left over in RAM.
+pub struct SwapVecIter<T> {
+    file: Option<File>,
+    batch_info: std::vec::IntoIter<BatchInfo>,
+    current_batch: std::vec::IntoIter<T>,
+    vector: VecDeque<T>,
+    config: SwapVecConfig,
+    pending_error: Option<SwapVecError>,
+}
+
+impl<T: Serialize + for<'a> Deserialize<'a>> SwapVecIter<T> {
+    pub(crate) fn new(
+        tempfile: Option<CheckedFile>,
+        vector: VecDeque<T>,
+        config: SwapVecConfig,
+        pending_error: Option<SwapVecError>,
+    ) -> Self {
+        let (file, batch_info) = match tempfile {
+            Some(mut checked_file) => {
+                checked_file
+                    .file
+                    .seek(SeekFrom::Start(0))
+                    .expect("spill file must be seekable");
+                (Some(checked_file.file), checked_file.batch_info.into_iter())
+            }
+            None => (None, Vec::new().into_iter()),
+        };
+        Self {
+            file,
+            batch_info,
+            current_batch: Vec::new().into_iter(),
+            vector,
+            config,
+            pending_error,
+        }
+    }
+
+    /// Read and decode the next batch from the spill file, if any.
+    /// Returns `Ok(false)` once there are no more batches on disk.
+    fn advance_batch(&mut self) -> Result<bool, SwapVecError> {
+        let info = match self.batch_info.next() {
+            Some(info) => info,
+            None => return Ok(false),
+        };
+        let file = self
+            .file
+            .as_mut()
+            .expect("batch_info is only non-empty when a spill file was opened");
+
+        let mut buffer = vec![0u8; info.bytes];
+        file.read_exact(&mut buffer)?;
+
+        if self.config.encryption.is_none()
+            && merkle::leaf_hash(&buffer, self.config.integrity) != info.hash
+        {
+            return Err(SwapVecError::WrongChecksum);
+        }
+
+        let unsealed = match self.config.encryption.as_ref() {
+            Some(encryption) => crypto::decrypt(encryption, buffer)?,
+            None => buffer,
+        };
+        let decompressed = self.config.compression.decompress(unsealed)?;
+        let batch: Vec<T> = bincode::deserialize(&decompressed)?;
+        self.current_batch = batch.into_iter();
+        Ok(true)
+    }
+}
+
+impl<T: Serialize + for<'a> Deserialize<'a>> Iterator for SwapVecIter<T> {
+    type Item = Result<T, SwapVecError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.pending_error.take() {
+            return Some(Err(error));
+        }
+        loop {
+            if let Some(element) = self.current_batch.next() {
+                return Some(Ok(element));
+            }
+            match self.advance_batch() {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        self.vector.pop_front().map(Ok)
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    fs::File,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+
+use serde::Serialize;
+
+use crate::{
+    compression::Compress,
+    crypto,
+    error::SwapVecError,
+    merkle,
+    swapvec::{BatchInfo, CheckedFile, Compression, Encryption, Integrity},
+};
+
+/// A serialized-and-compressed batch, tagged with the sequence
+/// number of the batch it was built from so the writer thread can
+/// put it back in push order.
+struct SerializedBatch {
+    seq: u64,
+    bytes: Vec<u8>,
+}
+
+/// Channel end workers pull filled batches from, shared across the
+/// worker pool behind a `Mutex` since `Receiver` isn't `Sync`.
+type BatchReceiver<T> = Arc<Mutex<Receiver<(u64, Vec<T>)>>>;
+
+/// Orders `SerializedBatch`es by ascending `seq`, reversed so a
+/// `BinaryHeap` (a max-heap) behaves like a min-heap.
+struct PendingBatch(SerializedBatch);
+
+impl PartialEq for PendingBatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.seq == other.0.seq
+    }
+}
+impl Eq for PendingBatch {}
+impl PartialOrd for PendingBatch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for PendingBatch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.seq.cmp(&self.0.seq)
+    }
+}
+
+/// Threaded pipeline that takes ownership of batches as they fill
+/// up and spills them to disk off the calling thread.
+///
+/// A pool of worker threads serializes and compresses batches in
+/// parallel; a single writer thread reassembles the results in
+/// push order (via a min-heap keyed on the batch sequence number)
+/// and writes the contiguous prefix that's ready. Any error raised
+/// by a worker or the writer is stashed in `error` and surfaced the
+/// next time the caller checks in.
+pub(crate) struct WriterPipeline<T> {
+    batches_tx: Sender<(u64, Vec<T>)>,
+    workers: Vec<JoinHandle<()>>,
+    writer: Option<JoinHandle<(File, Vec<BatchInfo>)>>,
+    error: Arc<Mutex<Option<SwapVecError>>>,
+}
+
+/// Everything [`WriterPipeline::start`] needs to spin up a
+/// generation, bundled into one struct since it's otherwise an
+/// unwieldy number of arguments.
+pub(crate) struct WriterPipelineConfig {
+    pub(crate) file: File,
+    pub(crate) worker_threads: usize,
+    pub(crate) compression: Option<Compression>,
+    pub(crate) encryption: Option<Encryption>,
+    pub(crate) write_buffer_bytes: usize,
+    pub(crate) integrity: Integrity,
+    /// Replayed as-is; see [`WriterPipeline::start`].
+    pub(crate) existing_batch_info: Vec<BatchInfo>,
+    /// Where this generation's sequence numbers pick up; see
+    /// [`WriterPipeline::start`].
+    pub(crate) start_seq: u64,
+}
+
+impl<T> WriterPipeline<T>
+where
+    T: Serialize + Send + 'static,
+{
+    /// Start a pipeline generation, resuming from whatever a
+    /// previous generation over the same spill file already wrote:
+    /// `existing_batch_info` is replayed as-is, and `start_seq` (the
+    /// number of batches already on disk) is where this generation's
+    /// sequence numbers pick up, so reordering stays monotonic
+    /// across a `flush`/push/`flush` cycle instead of restarting at
+    /// 0 and colliding with the first generation's batches.
+    pub(crate) fn start(config: WriterPipelineConfig) -> Self {
+        let WriterPipelineConfig {
+            file,
+            worker_threads,
+            compression,
+            encryption,
+            write_buffer_bytes,
+            integrity,
+            existing_batch_info,
+            start_seq,
+        } = config;
+        let worker_threads = worker_threads.max(1);
+
+        let (batches_tx, batches_rx) = mpsc::channel::<(u64, Vec<T>)>();
+        let batches_rx: BatchReceiver<T> = Arc::new(Mutex::new(batches_rx));
+        let (results_tx, results_rx) = mpsc::channel::<Result<SerializedBatch, SwapVecError>>();
+        let error = Arc::new(Mutex::new(None));
+
+        let workers = (0..worker_threads)
+            .map(|_| {
+                let batches_rx = Arc::clone(&batches_rx);
+                let results_tx = results_tx.clone();
+                std::thread::spawn(move || {
+                    worker_loop(batches_rx, results_tx, compression, encryption)
+                })
+            })
+            .collect();
+        // Drop our own sender so the writer thread's `for` loop over
+        // `results_rx` ends once every worker has dropped theirs.
+        drop(results_tx);
+
+        let writer_error = Arc::clone(&error);
+        let writer = std::thread::spawn(move || {
+            writer_loop(
+                file,
+                results_rx,
+                writer_error,
+                write_buffer_bytes,
+                integrity,
+                existing_batch_info,
+                start_seq,
+            )
+        });
+
+        Self {
+            batches_tx,
+            workers,
+            writer: Some(writer),
+            error,
+        }
+    }
+
+    /// Hand a filled batch over to the pipeline, tagged with its
+    /// global sequence number (`seq`). Never blocks on
+    /// serialization, compression or IO.
+    pub(crate) fn submit(&mut self, seq: u64, batch: Vec<T>) -> Result<(), SwapVecError> {
+        self.check_error()?;
+        // A send can only fail if every worker has died, in which
+        // case `check_error` above already returned the real cause
+        // on the next call; until then, report it as `Other`.
+        self.batches_tx
+            .send((seq, batch))
+            .map_err(|_| SwapVecError::Other)
+    }
+
+    /// Surface any error recorded by a worker or the writer thread
+    /// without waiting for the pipeline to finish.
+    pub(crate) fn check_error(&self) -> Result<(), SwapVecError> {
+        match self.error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Stop accepting batches, wait for every worker and the writer
+    /// thread to drain, and hand back the finished spill file.
+    pub(crate) fn finish(mut self) -> Result<(File, Vec<BatchInfo>), SwapVecError> {
+        let WriterPipeline {
+            batches_tx,
+            workers,
+            writer,
+            ..
+        } = &mut self;
+        drop(std::mem::replace(batches_tx, mpsc::channel().0));
+        for worker in workers.drain(..) {
+            let _ = worker.join();
+        }
+        let (file, batch_info) = writer
+            .take()
+            .expect("finish is only ever called once")
+            .join()
+            .map_err(|_| SwapVecError::Other)?;
+        self.check_error()?;
+        Ok((file, batch_info))
+    }
+}
+
+fn worker_loop<T: Serialize>(
+    batches_rx: BatchReceiver<T>,
+    results_tx: Sender<Result<SerializedBatch, SwapVecError>>,
+    compression: Option<Compression>,
+    encryption: Option<Encryption>,
+) {
+    // Reused across every batch this worker handles; the
+    // compressed/encrypted output still needs a fresh buffer since
+    // it's handed off to the writer thread over `results_tx`.
+    let mut serialize_buf = Vec::new();
+
+    loop {
+        let next = { batches_rx.lock().unwrap().recv() };
+        let (seq, batch) = match next {
+            Ok(item) => item,
+            Err(_) => break,
+        };
+
+        serialize_buf.clear();
+        let result = bincode::serialize_into(&mut serialize_buf, &batch)
+            .map_err(SwapVecError::from)
+            .map(|()| {
+                let mut compressed = Vec::new();
+                compression.compress_into(&serialize_buf, &mut compressed);
+                compressed
+            })
+            .map(|compressed| match encryption.as_ref() {
+                Some(encryption) => crypto::encrypt(encryption, seq, &compressed),
+                None => compressed,
+            })
+            .map(|bytes| SerializedBatch { seq, bytes });
+        if results_tx.send(result).is_err() {
+            break;
+        }
+    }
+}
+
+fn writer_loop(
+    file: File,
+    results_rx: Receiver<Result<SerializedBatch, SwapVecError>>,
+    error: Arc<Mutex<Option<SwapVecError>>>,
+    write_buffer_bytes: usize,
+    integrity: Integrity,
+    existing_batch_info: Vec<BatchInfo>,
+    start_seq: u64,
+) -> (File, Vec<BatchInfo>) {
+    let mut checked_file = CheckedFile::from_parts(file, existing_batch_info, write_buffer_bytes);
+    let mut pending: BinaryHeap<PendingBatch> = BinaryHeap::new();
+    let mut next_seq = start_seq;
+
+    for result in &results_rx {
+        match result {
+            Err(err) => {
+                *error.lock().unwrap() = Some(err);
+                continue;
+            }
+            Ok(batch) => pending.push(PendingBatch(batch)),
+        }
+        while pending.peek().is_some_and(|top| top.0.seq == next_seq) {
+            let batch = pending.pop().unwrap().0;
+            let hash = merkle::leaf_hash(&batch.bytes, integrity);
+            if let Err(err) = checked_file.write_all(&batch.bytes, hash) {
+                *error.lock().unwrap() = Some(err.into());
+            }
+            next_seq += 1;
+        }
+    }
+
+    if let Err(err) = checked_file.flush_buffer() {
+        *error.lock().unwrap() = Some(err.into());
+    }
+
+    (checked_file.file, checked_file.batch_info)
+}